@@ -17,9 +17,14 @@ pub enum CigarOp {
     Mismatch(u32),
     Insertion(u32),
     Deletion(u32),
+    SoftClip(u32),
+    HardClip(u32),
 }
 
 impl CigarOp {
+    /// The same op as seen from the other read: insertions/deletions swap,
+    /// while clips are unaffected since they only ever
+    /// describe query bases that were trimmed off, not reference bases.
     pub fn reverse(&self) -> Self {
         match self {
             Self::Insertion(l) => Self::Deletion(*l),
@@ -34,6 +39,8 @@ impl CigarOp {
             Self::Mismatch(l) => *l,
             Self::Insertion(l) => *l,
             Self::Deletion(l) => *l,
+            Self::SoftClip(l) => *l,
+            Self::HardClip(l) => *l,
         }
     }
 
@@ -43,8 +50,15 @@ impl CigarOp {
             Self::Mismatch(_) => CigarOp::Mismatch(length),
             Self::Insertion(_) => CigarOp::Insertion(length),
             Self::Deletion(_) => CigarOp::Deletion(length),
+            Self::SoftClip(_) => CigarOp::SoftClip(length),
+            Self::HardClip(_) => CigarOp::HardClip(length),
         }
     }
+
+    /// Clips only ever consume query bases, never the reference.
+    pub fn is_clip(&self) -> bool {
+        matches!(self, Self::SoftClip(_) | Self::HardClip(_))
+    }
 }
 
 impl From<(u32, char)> for CigarOp {
@@ -54,6 +68,8 @@ impl From<(u32, char)> for CigarOp {
             'X' => CigarOp::Mismatch(cigar.0),
             'I' => CigarOp::Insertion(cigar.0),
             'D' => CigarOp::Deletion(cigar.0),
+            'S' => CigarOp::SoftClip(cigar.0),
+            'H' => CigarOp::HardClip(cigar.0),
             _ => panic!("Invalid cigar op {}", cigar.1),
         }
     }
@@ -66,6 +82,8 @@ impl ToString for CigarOp {
             CigarOp::Mismatch(l) => format!("{}{}", l, 'X'),
             CigarOp::Deletion(l) => format!("{}{}", l, 'D'),
             CigarOp::Insertion(l) => format!("{}{}", l, 'I'),
+            CigarOp::SoftClip(l) => format!("{}{}", l, 'S'),
+            CigarOp::HardClip(l) => format!("{}{}", l, 'H'),
         }
     }
 }
@@ -74,6 +92,108 @@ pub fn cigar_to_string(cigar: &[CigarOp]) -> String {
     cigar.iter().map(|op| op.to_string()).collect()
 }
 
+/// Builds a SAM MD tag from an extended CIGAR (`Match`/`Mismatch` already
+/// separated), so a consumer that only stores `M` ops can still recover the
+/// mismatched reference bases without realigning.
+pub fn cigar_to_md(cigar: &[CigarOp], target: &[u8]) -> String {
+    let mut md = String::new();
+    let (mut run, mut tpos) = (0u32, 0usize);
+
+    for op in cigar {
+        match op {
+            CigarOp::Match(l) => {
+                run += l;
+                tpos += *l as usize;
+            }
+            CigarOp::Mismatch(l) => {
+                for _ in 0..*l {
+                    md.push_str(&run.to_string());
+                    md.push(target[tpos] as char);
+                    run = 0;
+                    tpos += 1;
+                }
+            }
+            CigarOp::Deletion(l) => {
+                md.push_str(&run.to_string());
+                md.push('^');
+                md.push_str(&String::from_utf8_lossy(&target[tpos..tpos + *l as usize]));
+                run = 0;
+                tpos += *l as usize;
+            }
+            CigarOp::Insertion(_) | CigarOp::SoftClip(_) | CigarOp::HardClip(_) => {}
+        }
+    }
+    md.push_str(&run.to_string());
+
+    md
+}
+
+/// Inverse of `cigar_to_md`: upgrades a plain SAM cigar (ambiguous `M`) paired
+/// with its MD tag into the crate's `=`/`X` representation, so externally
+/// supplied `M`+MD alignments can be ingested without realigning.
+pub fn md_to_cigar(cigar: &[(u32, char)], md: &str) -> Vec<CigarOp> {
+    let flags = parse_md_match_flags(md);
+    let mut idx = 0;
+    let mut ops = Vec::new();
+
+    for &(len, op) in cigar {
+        match op {
+            'I' => ops.push(CigarOp::Insertion(len)),
+            'D' => ops.push(CigarOp::Deletion(len)),
+            'S' => ops.push(CigarOp::SoftClip(len)),
+            'H' => ops.push(CigarOp::HardClip(len)),
+            'M' => {
+                let mut consumed = 0;
+                while consumed < len {
+                    let is_match = flags[idx];
+                    let start = consumed;
+                    while consumed < len && flags.get(idx) == Some(&is_match) {
+                        consumed += 1;
+                        idx += 1;
+                    }
+                    let run_len = consumed - start;
+                    ops.push(if is_match {
+                        CigarOp::Match(run_len)
+                    } else {
+                        CigarOp::Mismatch(run_len)
+                    });
+                }
+            }
+            _ => panic!("Unsupported SAM cigar op '{}' for MD upgrade", op),
+        }
+    }
+
+    ops
+}
+
+/// Flattens an MD tag into one match/mismatch flag per `M`-covered reference
+/// base, in order; `^`-deleted bases are consumed but not emitted.
+fn parse_md_match_flags(md: &str) -> Vec<bool> {
+    let mut flags = Vec::new();
+    let mut chars = md.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_ascii_digit() {
+            let mut n = 0usize;
+            while let Some(&d) = chars.peek().filter(|d| d.is_ascii_digit()) {
+                n = n * 10 + d.to_digit(10).unwrap() as usize;
+                chars.next();
+            }
+            flags.extend(std::iter::repeat(true).take(n));
+        } else if c == '^' {
+            chars.next();
+            while chars.peek().is_some_and(|d| d.is_ascii_alphabetic()) {
+                chars.next();
+            }
+        } else {
+            chars.next();
+            flags.push(false);
+        }
+    }
+
+    flags
+}
+
 #[inline]
 fn complement(base: u8) -> u8 {
     match base {
@@ -98,6 +218,11 @@ pub fn align_overlaps(overlaps: &mut [Overlap], reads: &[HAECRecord]) {
         //.with_min_len(10)
         .progress_count(n_overlaps as u64)
         .for_each_with(aligners, |aligners, o| {
+            if o.cigar.is_some() {
+                // Already aligned from a trusted PAF `cg:Z:` tag; nothing to do.
+                return;
+            }
+
             let aligner = aligners.get_or(|| wfa::WFAAligner::default());
 
             let query = &reads[o.qid as usize].seq[o.qstart as usize..o.qend as usize];
@@ -129,7 +254,7 @@ pub fn align_overlaps(overlaps: &mut [Overlap], reads: &[HAECRecord]) {
         });
 }
 
-fn calculate_accuracy(cigar: &[CigarOp]) -> f32 {
+pub(crate) fn calculate_accuracy(cigar: &[CigarOp]) -> f32 {
     let (mut matches, mut subs, mut ins, mut dels) = (0u32, 0u32, 0u32, 0u32);
     for op in cigar {
         match op {
@@ -137,6 +262,7 @@ fn calculate_accuracy(cigar: &[CigarOp]) -> f32 {
             CigarOp::Mismatch(l) => subs += l,
             CigarOp::Insertion(l) => ins += l,
             CigarOp::Deletion(l) => dels += l,
+            CigarOp::SoftClip(_) | CigarOp::HardClip(_) => {}
         };
     }
 
@@ -186,6 +312,12 @@ pub(crate) fn fix_cigar(cigar: &mut Vec<CigarOp>, target: &[u8], query: &[u8]) -
         if let CigarOp::Match(l) | CigarOp::Mismatch(l) = &cigar[i] {
             tpos += *l as usize;
             qpos += *l as usize;
+        } else if cigar[i].is_clip() {
+            // Clips only ever sit at the ends of the CIGAR, outside the
+            // aligned region this function left-shifts, so they bound the
+            // alignment rather than being treated as a shiftable indel and
+            // must not advance tpos/qpos (soft clips do consume query bases,
+            // but not from the `target`/`query` slices indexed here).
         } else {
             if i > 0
                 && i < cigar.len() - 1
@@ -265,6 +397,12 @@ pub(crate) fn fix_cigar(cigar: &mut Vec<CigarOp>, target: &[u8], query: &[u8]) -
                     tshift = *l;
                     return false;
                 }
+                CigarOp::SoftClip(_) | CigarOp::HardClip(_) => {
+                    // A clip is a hard boundary, not trimmable flank: stop the
+                    // leading-trim pass here but keep the op itself.
+                    is_start = false;
+                    return true;
+                }
             }
         }
 
@@ -293,7 +431,37 @@ pub(crate) fn fix_cigar(cigar: &mut Vec<CigarOp>, target: &[u8], query: &[u8]) -
 
 #[cfg(test)]
 mod tests {
-    use super::{fix_cigar, CigarOp};
+    use super::{cigar_to_md, fix_cigar, md_to_cigar, CigarOp};
+
+    #[test]
+    fn cigar_to_md_handles_mismatches_and_deletions() {
+        let target = "ACGTACGTAC".as_bytes();
+        let cigar = vec![
+            CigarOp::Match(4),
+            CigarOp::Mismatch(1),
+            CigarOp::Deletion(2),
+            CigarOp::Insertion(3),
+            CigarOp::Match(3),
+        ];
+
+        assert_eq!(cigar_to_md(&cigar, target), "4A0^CG3");
+    }
+
+    #[test]
+    fn md_to_cigar_upgrades_plain_m_cigar() {
+        let cigar = vec![(4u32, 'M'), (1, 'M'), (2, 'D'), (3, 'I'), (3, 'M')];
+
+        assert_eq!(
+            md_to_cigar(&cigar, "4A0^CG3"),
+            [
+                CigarOp::Match(4),
+                CigarOp::Mismatch(1),
+                CigarOp::Deletion(2),
+                CigarOp::Insertion(3),
+                CigarOp::Match(3),
+            ]
+        );
+    }
 
     #[test]
     fn fix_cigar_test1() {
@@ -0,0 +1,43 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+};
+
+pub struct HAECRecord {
+    pub id: String,
+    pub seq: Vec<u8>,
+}
+
+impl HAECRecord {
+    pub fn new(id: String, seq: Vec<u8>) -> Self {
+        HAECRecord { id, seq }
+    }
+}
+
+/// Reads a (FASTA) file of reads, keyed by the id following `>`.
+pub fn read_reads(path: &str) -> Vec<HAECRecord> {
+    let reader = BufReader::new(File::open(path).expect("Cannot open reads file"));
+
+    let mut records = Vec::new();
+    let (mut id, mut seq) = (None::<String>, Vec::new());
+
+    for line in reader.lines() {
+        let line = line.expect("Cannot read reads file");
+
+        if let Some(header) = line.strip_prefix('>') {
+            if let Some(id) = id.take() {
+                records.push(HAECRecord::new(id, std::mem::take(&mut seq)));
+            }
+
+            id = Some(header.split_whitespace().next().unwrap_or("").to_owned());
+        } else {
+            seq.extend_from_slice(line.trim_end().as_bytes());
+        }
+    }
+
+    if let Some(id) = id {
+        records.push(HAECRecord::new(id, seq));
+    }
+
+    records
+}
@@ -1,6 +1,6 @@
 use clap::{Args, Parser, Subcommand};
 
-use ont_haec_rs::{error_correction, generate_features};
+use ont_haec_rs::{error_correction, generate_features, sam::align_and_write_sam};
 
 #[derive(Parser)]
 #[command(author, version, about)]
@@ -22,12 +22,19 @@ struct Cli {
 
     #[arg(global = true)]
     output: String,
+
+    /// Trust a `cg:Z:` CIGAR already present in the overlap file instead of
+    /// realigning every overlap with WFA.
+    #[arg(long, default_value_t = false, global = true)]
+    trust_cigar: bool,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     Features,
     Inference(InferenceArgs),
+    /// Align every overlap and write the result as SAM, for inspection in IGV.
+    Align,
 }
 
 #[derive(Args)]
@@ -50,6 +57,7 @@ fn main() {
                 &cli.output,
                 cli.feat_gen_threads,
                 cli.window_size,
+                cli.trust_cigar,
             );
         }
         Commands::Inference(args) => error_correction(
@@ -60,6 +68,10 @@ fn main() {
             cli.feat_gen_threads,
             cli.window_size,
             &args.devices,
+            cli.trust_cigar,
         ),
+        Commands::Align => {
+            align_and_write_sam(&cli.reads, &cli.overlaps, &cli.output, cli.trust_cigar);
+        }
     }
 }
@@ -0,0 +1,84 @@
+use std::{
+    fs::File,
+    io::{self, Read},
+};
+
+use flate2::read::MultiGzDecoder;
+use rayon::prelude::*;
+
+const BGZF_MAGIC: [u8; 4] = [0x1f, 0x8b, 0x08, 0x04];
+
+fn is_bgzf(path: &str) -> io::Result<bool> {
+    let mut magic = [0u8; 4];
+    match File::open(path)?.read_exact(&mut magic) {
+        Ok(()) => Ok(magic == BGZF_MAGIC),
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Splits a BGZF stream into the byte ranges of its individual blocks by
+/// reading the `BC` extra-field subfield (BSIZE) out of each gzip member
+/// header, so the blocks can be decompressed independently and in parallel.
+fn scan_block_ranges(data: &[u8]) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let mut pos = 0;
+
+    while pos < data.len() {
+        let xlen = u16::from_le_bytes([data[pos + 10], data[pos + 11]]) as usize;
+        let extra = &data[pos + 12..pos + 12 + xlen];
+        let bsize = find_bc_subfield(extra).expect("BGZF block missing BC subfield");
+
+        let block_len = bsize + 1;
+        ranges.push((pos, pos + block_len));
+        pos += block_len;
+    }
+
+    ranges
+}
+
+/// Walks the gzip extra field's `SI1,SI2,SLEN,data` subfields looking for the
+/// BGZF `BC` subfield (`SLEN == 2`), returning BSIZE from its 2-byte payload.
+fn find_bc_subfield(mut extra: &[u8]) -> Option<usize> {
+    while extra.len() >= 4 {
+        let (si1, si2) = (extra[0], extra[1]);
+        let slen = u16::from_le_bytes([extra[2], extra[3]]) as usize;
+        let data = &extra[4..4 + slen];
+
+        if si1 == b'B' && si2 == b'C' {
+            return Some(u16::from_le_bytes([data[0], data[1]]) as usize);
+        }
+
+        extra = &extra[4 + slen..];
+    }
+
+    None
+}
+
+fn decompress_block(block: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    MultiGzDecoder::new(block)
+        .read_to_end(&mut out)
+        .expect("Cannot decompress BGZF block");
+
+    out
+}
+
+/// Reads `path` fully into memory, transparently decompressing it if it is
+/// BGZF-compressed (detected by magic). BGZF blocks are independent gzip
+/// members, so they are decompressed in parallel with rayon and concatenated
+/// in order; a non-BGZF file is read as-is. No `.gzi` index is read and there
+/// is no seeking to an offset: the whole file is always read.
+pub(crate) fn read_bytes(path: &str) -> io::Result<Vec<u8>> {
+    if !is_bgzf(path)? {
+        return std::fs::read(path);
+    }
+
+    let compressed = std::fs::read(path)?;
+
+    Ok(scan_block_ranges(&compressed)
+        .into_par_iter()
+        .map(|(s, e)| decompress_block(&compressed[s..e]))
+        .collect::<Vec<_>>()
+        .concat())
+}
@@ -0,0 +1,228 @@
+use crate::{
+    aligners::{calculate_accuracy, CigarOp},
+    haec_io::HAECRecord,
+};
+
+mod bgzf;
+
+#[derive(Debug, PartialEq, Clone, Copy, Eq)]
+pub enum Strand {
+    Forward,
+    Reverse,
+}
+
+#[derive(Debug, Clone)]
+pub struct Overlap {
+    pub qid: u32,
+    pub qlen: u32,
+    pub qstart: u32,
+    pub qend: u32,
+    pub strand: Strand,
+
+    pub tid: u32,
+    pub tlen: u32,
+    pub tstart: u32,
+    pub tend: u32,
+
+    pub cigar: Option<Vec<CigarOp>>,
+    pub accuracy: Option<f32>,
+}
+
+/// Whether `align_overlaps` should trust a `cg:Z:` CIGAR already present in the
+/// input PAF, or discard it and realign every overlap with WFA.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CigarSource {
+    TrustPaf,
+    Realign,
+}
+
+/// Parses a PAF file into `Overlap`s, resolving read names against `name_to_id`.
+///
+/// `path` is read transparently whether it is plain text or BGZF-compressed
+/// (detected by magic), so the `Features`/`Inference` commands can read a
+/// BGZF-compressed PAF without a separate decompress-to-temp step. The whole
+/// file is decompressed into memory; a plain (non-BGZF) gzip file is not
+/// decompressed.
+///
+/// When `cigar_source` is `CigarSource::TrustPaf` and a record carries a `cg:Z:`
+/// tag, the CIGAR is parsed and attached to the overlap (with trimmed
+/// coordinates and accuracy already filled in), so `align_overlaps` can skip it.
+pub fn parse_paf(
+    path: &str,
+    reads: &[HAECRecord],
+    name_to_id: &std::collections::HashMap<&str, u32>,
+    cigar_source: CigarSource,
+) -> Vec<Overlap> {
+    let text = bgzf::read_bytes(path).expect("Cannot read overlaps file");
+
+    String::from_utf8(text)
+        .expect("Overlaps file is not valid UTF-8")
+        .lines()
+        .filter_map(|l| parse_paf_line(l, reads, name_to_id, cigar_source))
+        .collect()
+}
+
+pub(crate) fn parse_paf_line(
+    line: &str,
+    reads: &[HAECRecord],
+    name_to_id: &std::collections::HashMap<&str, u32>,
+    cigar_source: CigarSource,
+) -> Option<Overlap> {
+    let fields: Vec<&str> = line.split('\t').collect();
+
+    let qid = *name_to_id.get(fields[0])?;
+    let qstart: u32 = fields[2].parse().unwrap();
+    let qend: u32 = fields[3].parse().unwrap();
+    let strand = match fields[4] {
+        "+" => Strand::Forward,
+        "-" => Strand::Reverse,
+        _ => panic!("Invalid strand in PAF line"),
+    };
+
+    let tid = *name_to_id.get(fields[5])?;
+    let tstart: u32 = fields[7].parse().unwrap();
+    let tend: u32 = fields[8].parse().unwrap();
+
+    let mut overlap = Overlap {
+        qid,
+        qlen: reads[qid as usize].seq.len() as u32,
+        qstart,
+        qend,
+        strand,
+        tid,
+        tlen: reads[tid as usize].seq.len() as u32,
+        tstart,
+        tend,
+        cigar: None,
+        accuracy: None,
+    };
+
+    if cigar_source == CigarSource::TrustPaf {
+        if let Some(cg) = fields[12..].iter().find_map(|f| f.strip_prefix("cg:Z:")) {
+            let target = &reads[tid as usize].seq[tstart as usize..tend as usize];
+            let query_seq = &reads[qid as usize].seq[qstart as usize..qend as usize];
+            let query = match strand {
+                Strand::Forward => std::borrow::Cow::Borrowed(query_seq),
+                Strand::Reverse => std::borrow::Cow::Owned(crate::aligners::reverse_complement(query_seq)),
+            };
+
+            let cigar = paf_cigar_to_ops(cg, target, &query);
+            overlap.accuracy = Some(calculate_accuracy(&cigar));
+            overlap.cigar = Some(cigar);
+        }
+    }
+
+    Some(overlap)
+}
+
+/// Parses a PAF `cg:Z:` string (e.g. `10M2I5M1D3M`) into `CigarOp`s, splitting
+/// every ambiguous `M` run into `Match`/`Mismatch` ops by comparing `target`
+/// and `query` base by base. `I`/`D`/`=`/`X` map one-to-one and only advance
+/// their respective cursor(s).
+pub fn paf_cigar_to_ops(cg: &str, target: &[u8], query: &[u8]) -> Vec<CigarOp> {
+    let mut ops = Vec::new();
+    let (mut tpos, mut qpos) = (0usize, 0usize);
+    let mut len = 0u32;
+
+    for c in cg.chars() {
+        if c.is_ascii_digit() {
+            len = len * 10 + c.to_digit(10).unwrap();
+            continue;
+        }
+
+        match c {
+            'M' => split_match_run(len, target, query, &mut tpos, &mut qpos, &mut ops),
+            '=' => {
+                ops.push(CigarOp::Match(len));
+                tpos += len as usize;
+                qpos += len as usize;
+            }
+            'X' => {
+                ops.push(CigarOp::Mismatch(len));
+                tpos += len as usize;
+                qpos += len as usize;
+            }
+            'I' => {
+                ops.push(CigarOp::Insertion(len));
+                qpos += len as usize;
+            }
+            'D' => {
+                ops.push(CigarOp::Deletion(len));
+                tpos += len as usize;
+            }
+            _ => panic!("Invalid cg:Z: op '{}'", c),
+        }
+
+        len = 0;
+    }
+
+    ops
+}
+
+fn split_match_run(
+    len: u32,
+    target: &[u8],
+    query: &[u8],
+    tpos: &mut usize,
+    qpos: &mut usize,
+    ops: &mut Vec<CigarOp>,
+) {
+    let mut run_len = 0u32;
+    let mut run_is_match = true;
+
+    for i in 0..len {
+        let is_match = target[*tpos + i as usize] == query[*qpos + i as usize];
+
+        if run_len > 0 && is_match != run_is_match {
+            push_run(ops, run_is_match, run_len);
+            run_len = 0;
+        }
+
+        run_is_match = is_match;
+        run_len += 1;
+    }
+
+    if run_len > 0 {
+        push_run(ops, run_is_match, run_len);
+    }
+
+    *tpos += len as usize;
+    *qpos += len as usize;
+}
+
+fn push_run(ops: &mut Vec<CigarOp>, is_match: bool, len: u32) {
+    ops.push(if is_match {
+        CigarOp::Match(len)
+    } else {
+        CigarOp::Mismatch(len)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paf_cigar_splits_mismatches_out_of_m_run() {
+        let target = b"ACGTACGT";
+        let query = b"ACGTTCGT";
+
+        let ops = paf_cigar_to_ops("8M", target, query);
+        assert_eq!(
+            ops,
+            [CigarOp::Match(4), CigarOp::Mismatch(1), CigarOp::Match(3)]
+        );
+    }
+
+    #[test]
+    fn paf_cigar_passes_through_indels() {
+        let target = b"ACGTACGT";
+        let query = b"ACGTACCGT";
+
+        let ops = paf_cigar_to_ops("5M1I3M", target, query);
+        assert_eq!(
+            ops,
+            [CigarOp::Match(5), CigarOp::Insertion(1), CigarOp::Match(3)]
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+};
+
+use crate::{
+    aligners::{align_overlaps, cigar_to_string, reverse_complement, CigarOp},
+    haec_io::{self, HAECRecord},
+    overlaps::{self, CigarSource, Overlap, Strand},
+};
+
+const FLAG_REVERSE: u16 = 0x10;
+
+/// Aligns every overlap in `overlaps_path` and writes the result as SAM,
+/// reusing the same realignment path as feature generation so overlaps can be
+/// inspected in IGV.
+pub fn align_and_write_sam(reads_path: &str, overlaps_path: &str, output: &str, trust_cigar: bool) {
+    let reads = haec_io::read_reads(reads_path);
+    let name_to_id = reads
+        .iter()
+        .enumerate()
+        .map(|(i, r)| (r.id.as_str(), i as u32))
+        .collect();
+
+    let cigar_source = if trust_cigar {
+        CigarSource::TrustPaf
+    } else {
+        CigarSource::Realign
+    };
+
+    let mut overlaps = overlaps::parse_paf(overlaps_path, &reads, &name_to_id, cigar_source);
+    align_overlaps(&mut overlaps, &reads);
+
+    write_sam(output, &overlaps, &reads).expect("Cannot write SAM output");
+}
+
+/// Writes `overlaps` as SAM records, with the target read of each overlap as
+/// the reference (`@SQ`/RNAME) and the query read as QNAME. `Strand::Reverse`
+/// sets the 0x10 flag, and leading/trailing soft-clips cover the query read's
+/// bases outside the overlap's aligned `qstart`/`qend`.
+pub fn write_sam(path: &str, overlaps: &[Overlap], reads: &[HAECRecord]) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+
+    writeln!(writer, "@HD\tVN:1.6\tSO:unsorted")?;
+    for read in reads {
+        writeln!(writer, "@SQ\tSN:{}\tLN:{}", read.id, read.seq.len())?;
+    }
+
+    for o in overlaps {
+        let Some(cigar) = o.cigar.as_ref() else {
+            continue;
+        };
+
+        write_record(&mut writer, o, cigar, reads)?;
+    }
+
+    Ok(())
+}
+
+fn write_record<W: Write>(
+    writer: &mut W,
+    o: &Overlap,
+    cigar: &[CigarOp],
+    reads: &[HAECRecord],
+) -> io::Result<()> {
+    let query_rec = &reads[o.qid as usize];
+    let target_rec = &reads[o.tid as usize];
+
+    let flag = match o.strand {
+        Strand::Forward => 0,
+        Strand::Reverse => FLAG_REVERSE,
+    };
+
+    let full_cigar = with_soft_clips(cigar, o);
+    let seq = full_query_seq(o, query_rec);
+
+    writeln!(
+        writer,
+        "{}\t{}\t{}\t{}\t255\t{}\t*\t0\t0\t{}\t*",
+        query_rec.id,
+        flag,
+        target_rec.id,
+        o.tstart + 1,
+        cigar_to_string(&full_cigar),
+        String::from_utf8_lossy(&seq),
+    )
+}
+
+/// Prepends/appends `SoftClip` ops for the read bases outside the aligned
+/// region, using the overlap's `qstart`/`qend` (as left by alignment) relative
+/// to the full read.
+/// `qstart`/`qend` are always forward-strand read coordinates (PAF
+/// convention), so on the reverse strand the flanks swap sides once SEQ is
+/// written out reverse-complemented.
+fn with_soft_clips(cigar: &[CigarOp], o: &Overlap) -> Vec<CigarOp> {
+    let (lead, trail) = match o.strand {
+        Strand::Forward => (o.qstart, o.qlen - o.qend),
+        Strand::Reverse => (o.qlen - o.qend, o.qstart),
+    };
+
+    let mut full = Vec::with_capacity(cigar.len() + 2);
+    if lead > 0 {
+        full.push(CigarOp::SoftClip(lead));
+    }
+    full.extend_from_slice(cigar);
+    if trail > 0 {
+        full.push(CigarOp::SoftClip(trail));
+    }
+
+    full
+}
+
+/// The SAM spec requires soft-clipped bases to be present in SEQ, so this
+/// returns the *whole* read (reverse-complemented for `Strand::Reverse`)
+/// rather than just the aligned slice.
+fn full_query_seq(o: &Overlap, query_rec: &HAECRecord) -> Vec<u8> {
+    match o.strand {
+        Strand::Forward => query_rec.seq.clone(),
+        Strand::Reverse => reverse_complement(&query_rec.seq),
+    }
+}